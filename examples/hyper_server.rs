@@ -18,8 +18,9 @@ extern crate hyper;
 extern crate futures;
 extern crate digest_headers;
 
+use digest_headers::DigestBuilder;
 use digest_headers::use_hyper::DigestHeader;
-use futures::{Future, Stream};
+use futures::{Future, IntoFuture, Stream};
 use hyper::server::{Http, Request, Response, Service};
 
 struct Responder;
@@ -37,19 +38,28 @@ impl Service for Responder {
             .remove::<DigestHeader>()
             .ok_or(hyper::Error::Header);
 
-        let fut = req
-            .body()
-            .concat2()
-            .join(digest)
-            .and_then(|(body, digest)| {
-                if digest.0.verify(&body).is_ok() {
-                    println!("Verified!");
-                    Ok(Response::new().with_body(body))
-                } else {
-                    println!("Bad Request!");
-                    Err(hyper::Error::Header)
-                }
-            });
+        let fut = digest.into_future().and_then(|digest| {
+            let builder = DigestBuilder::new(digest.0.sha_size());
+
+            // Fold each chunk into the digest as it arrives, collecting the bytes so the body
+            // can be echoed back to the client once it has been verified.
+            req.body()
+                .fold((Vec::new(), builder), |(mut bytes, mut builder), chunk| {
+                    builder.update(&chunk);
+                    bytes.extend_from_slice(&chunk);
+
+                    Ok::<_, hyper::Error>((bytes, builder))
+                })
+                .and_then(move |(body, builder)| {
+                    if digest.0 == builder.finalize() {
+                        println!("Verified!");
+                        Ok(Response::new().with_body(body))
+                    } else {
+                        println!("Bad Request!");
+                        Err(hyper::Error::Header)
+                    }
+                })
+        });
 
         Box::new(fut)
     }
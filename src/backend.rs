@@ -0,0 +1,114 @@
+/* This file is part of Digest Header
+ *
+ * Digest Header is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * Digest Header is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with Digest Header  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Pluggable hashing backends.
+//!
+//! The hashing implementation sits behind a small object-safe trait so that the algorithm used to
+//! produce a `Digest` can be swapped out without touching the public API. By default the SHA-2
+//! variants run through `ring`'s C/asm backend; with the `sha-2` cargo feature they run through
+//! the pure-Rust `sha2` crate instead. The SHA-3 variants always run through the pure-Rust `sha3`
+//! crate. Selecting `sha-2` does not remove the dependency on `ring`: the crate links it in every
+//! configuration, and `Digest::verify` uses its constant-time comparison.
+
+use ShaSize;
+
+/// An object-safe running hash.
+///
+/// This mirrors the shape of RustCrypto's `digest::DynDigest` (`update` a chunk, then
+/// `finalize_into_box`), but is kept internal so each backend can be adapted to it without leaking
+/// the concrete hasher types into the public API.
+pub(crate) trait DigestBackend {
+    /// Feed another chunk of the body into the running hash.
+    fn update(&mut self, chunk: &[u8]);
+
+    /// Consume the backend, producing the raw digest bytes.
+    fn finalize_into_box(self: Box<Self>) -> Box<[u8]>;
+}
+
+/// Selects a backend for the given `ShaSize`, honouring the enabled cargo features.
+pub(crate) fn for_size(size: ShaSize) -> Box<DigestBackend> {
+    match size {
+        ShaSize::TwoFiftySix => sha_256(),
+        ShaSize::ThreeEightyFour => sha_384(),
+        ShaSize::FiveTwelve => sha_512(),
+        #[cfg(feature = "sha-3")]
+        ShaSize::Sha3TwoFiftySix => Box::new(::sha3::Sha3_256::default()),
+        #[cfg(feature = "sha-3")]
+        ShaSize::Sha3ThreeEightyFour => Box::new(::sha3::Sha3_384::default()),
+        #[cfg(feature = "sha-3")]
+        ShaSize::Sha3FiveTwelve => Box::new(::sha3::Sha3_512::default()),
+    }
+}
+
+#[cfg(not(feature = "sha-2"))]
+fn sha_256() -> Box<DigestBackend> {
+    Box::new(Ring(::ring::digest::Context::new(&::ring::digest::SHA256)))
+}
+
+#[cfg(not(feature = "sha-2"))]
+fn sha_384() -> Box<DigestBackend> {
+    Box::new(Ring(::ring::digest::Context::new(&::ring::digest::SHA384)))
+}
+
+#[cfg(not(feature = "sha-2"))]
+fn sha_512() -> Box<DigestBackend> {
+    Box::new(Ring(::ring::digest::Context::new(&::ring::digest::SHA512)))
+}
+
+#[cfg(feature = "sha-2")]
+fn sha_256() -> Box<DigestBackend> {
+    Box::new(::sha2::Sha256::default())
+}
+
+#[cfg(feature = "sha-2")]
+fn sha_384() -> Box<DigestBackend> {
+    Box::new(::sha2::Sha384::default())
+}
+
+#[cfg(feature = "sha-2")]
+fn sha_512() -> Box<DigestBackend> {
+    Box::new(::sha2::Sha512::default())
+}
+
+/// Adapts `ring`'s incremental hashing `Context` to the `DigestBackend` trait.
+#[cfg(not(feature = "sha-2"))]
+struct Ring(::ring::digest::Context);
+
+#[cfg(not(feature = "sha-2"))]
+impl DigestBackend for Ring {
+    fn update(&mut self, chunk: &[u8]) {
+        self.0.update(chunk);
+    }
+
+    fn finalize_into_box(self: Box<Self>) -> Box<[u8]> {
+        Vec::from(self.0.finish().as_ref()).into_boxed_slice()
+    }
+}
+
+/// Adapts any RustCrypto `digest::Digest` (the `sha2`/`sha3` hashers) to the `DigestBackend` trait.
+#[cfg(any(feature = "sha-2", feature = "sha-3"))]
+impl<D> DigestBackend for D
+where
+    D: ::digest::Digest + 'static,
+{
+    fn update(&mut self, chunk: &[u8]) {
+        ::digest::Digest::update(self, chunk);
+    }
+
+    fn finalize_into_box(self: Box<Self>) -> Box<[u8]> {
+        (*self).finalize().as_slice().to_vec().into_boxed_slice()
+    }
+}
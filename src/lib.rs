@@ -78,12 +78,19 @@ extern crate base64;
 extern crate futures;
 #[cfg(feature = "use_hyper")]
 extern crate hyper;
+#[cfg(any(feature = "sha-2", feature = "sha-3"))]
+extern crate digest;
 extern crate ring;
 #[cfg(feature = "use_rocket")]
 extern crate rocket;
+#[cfg(feature = "sha-2")]
+extern crate sha2;
+#[cfg(feature = "sha-3")]
+extern crate sha3;
 #[cfg(feature = "use_hyper")]
 extern crate tokio_core;
 
+mod backend;
 mod error;
 pub mod prelude;
 #[cfg(feature = "use_hyper")]
@@ -94,18 +101,30 @@ pub mod use_rocket;
 pub use self::error::Error;
 
 use std::fmt;
+use std::io::{self, Write};
 use std::str::FromStr;
 
+use backend::DigestBackend;
+
 /// Defines variants for the size of SHA hash.
 ///
 /// Since this isn't being used for encryption or identification, it doesn't need to be very
 /// strong. That said, it's ultimately up to the user of this library, so we provide options for
 /// 256, 384, and 512.
+///
+/// When the `sha-3` feature is enabled, the SHA-3 (Keccak) variants become available as well, so
+/// that servers speaking the RFC 3230 `SHA3-256` token can interoperate without a separate crate.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ShaSize {
     TwoFiftySix,
     ThreeEightyFour,
     FiveTwelve,
+    #[cfg(feature = "sha-3")]
+    Sha3TwoFiftySix,
+    #[cfg(feature = "sha-3")]
+    Sha3ThreeEightyFour,
+    #[cfg(feature = "sha-3")]
+    Sha3FiveTwelve,
 }
 
 impl fmt::Display for ShaSize {
@@ -114,6 +133,12 @@ impl fmt::Display for ShaSize {
             ShaSize::TwoFiftySix => "SHA-256",
             ShaSize::ThreeEightyFour => "SHA-384",
             ShaSize::FiveTwelve => "SHA-512",
+            #[cfg(feature = "sha-3")]
+            ShaSize::Sha3TwoFiftySix => "SHA3-256",
+            #[cfg(feature = "sha-3")]
+            ShaSize::Sha3ThreeEightyFour => "SHA3-384",
+            #[cfg(feature = "sha-3")]
+            ShaSize::Sha3FiveTwelve => "SHA3-512",
         };
 
         write!(f, "{}", s)
@@ -128,6 +153,12 @@ impl FromStr for ShaSize {
             "SHA-256" => ShaSize::TwoFiftySix,
             "SHA-384" => ShaSize::ThreeEightyFour,
             "SHA-512" => ShaSize::FiveTwelve,
+            #[cfg(feature = "sha-3")]
+            "SHA3-256" => ShaSize::Sha3TwoFiftySix,
+            #[cfg(feature = "sha-3")]
+            "SHA3-384" => ShaSize::Sha3ThreeEightyFour,
+            #[cfg(feature = "sha-3")]
+            "SHA3-512" => ShaSize::Sha3FiveTwelve,
             _ => return Err(Error::ParseShaSize),
         };
 
@@ -147,16 +178,67 @@ impl<'a> RequestBody<'a> {
 
     /// Consumes the `RequestBody`, producing a `Digest`.
     pub fn digest(self, sha_size: ShaSize) -> Digest {
-        let size = match sha_size {
-            ShaSize::TwoFiftySix => &ring::digest::SHA256,
-            ShaSize::ThreeEightyFour => &ring::digest::SHA384,
-            ShaSize::FiveTwelve => &ring::digest::SHA512,
-        };
+        let mut builder = DigestBuilder::new(sha_size);
+        builder.update(self.0);
+        builder.finalize()
+    }
+}
 
-        let d = ring::digest::digest(size, self.0);
-        let b = base64::encode(&d);
+/// Computes a `Digest` incrementally from a body that arrives in chunks.
+///
+/// Rather than buffering a whole request into a single `&[u8]`, a `DigestBuilder` wraps the running
+/// state of the chosen algorithm. Callers feed chunks with `update` as they arrive and call
+/// `finalize` once the body is exhausted. The builder also implements `std::io::Write`, so it can
+/// be handed to anything that writes bytes.
+///
+/// # Example
+///
+/// ```rust
+/// # use digest_headers::{DigestBuilder, ShaSize};
+/// let mut builder = DigestBuilder::new(ShaSize::TwoFiftySix);
+///
+/// builder.update(b"Some ");
+/// builder.update(b"message");
+///
+/// let digest = builder.finalize();
+///
+/// assert!(digest.verify(b"Some message").is_ok());
+/// ```
+pub struct DigestBuilder {
+    size: ShaSize,
+    backend: Box<DigestBackend>,
+}
+
+impl DigestBuilder {
+    /// Creates a new `DigestBuilder` for the given `ShaSize`.
+    pub fn new(size: ShaSize) -> Self {
+        DigestBuilder {
+            size,
+            backend: backend::for_size(size),
+        }
+    }
 
-        Digest::from_base64_and_size(b, sha_size)
+    /// Feed another chunk of the body into the running hash.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.backend.update(chunk);
+    }
+
+    /// Consume the builder, producing the finished `Digest`.
+    pub fn finalize(self) -> Digest {
+        let b = base64::encode(&self.backend.finalize_into_box());
+
+        Digest::from_base64_and_size(b, self.size)
+    }
+}
+
+impl Write for DigestBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
@@ -225,6 +307,58 @@ impl Digest {
         format!("{}={}", self.size, self.digest)
     }
 
+    /// Represents the `Digest` as an RFC 9530 structured-field dictionary member.
+    ///
+    /// Where `as_string` emits the legacy RFC 3230 `SHA-256=base64` syntax, this emits the modern
+    /// `Content-Digest`/`Repr-Digest` form used by newer HTTP stacks: the algorithm key is
+    /// lowercased and the base64 value is wrapped in colons as a byte sequence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use digest_headers::Digest;
+    /// let digest = "SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE="
+    ///     .parse::<Digest>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     digest.as_structured_field(),
+    ///     "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:"
+    /// );
+    /// ```
+    pub fn as_structured_field(&self) -> String {
+        format!("{}=:{}:", self.size.to_string().to_lowercase(), self.digest)
+    }
+
+    /// Parses a single RFC 9530 structured-field dictionary member into a `Digest`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use digest_headers::Digest;
+    /// let raw = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+    /// let digest = Digest::parse_structured_field(raw).unwrap();
+    ///
+    /// assert_eq!(digest.as_string(), "SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=");
+    /// ```
+    pub fn parse_structured_field(s: &str) -> Result<Self, Error> {
+        let eq_index = s.find('=').ok_or(Error::ParseDigest)?;
+        let (key, rest) = s.split_at(eq_index);
+
+        let size = key.trim().to_uppercase().parse()?;
+
+        let value = rest.get(1..).ok_or(Error::ParseDigest)?.trim();
+
+        if value.len() < 2 || !value.starts_with(':') || !value.ends_with(':') {
+            return Err(Error::ParseDigest);
+        }
+
+        Ok(Digest {
+            digest: value[1..value.len() - 1].to_owned(),
+            size,
+        })
+    }
+
     /// Verify a given message body with the digest.
     ///
     /// # Example
@@ -239,11 +373,14 @@ impl Digest {
     pub fn verify(&self, body: &[u8]) -> Result<(), Error> {
         let digest = Digest::new(body, self.size);
 
-        if *self == digest {
-            Ok(())
-        } else {
-            Err(Error::InvalidDigest)
-        }
+        // Compare the raw bytes in constant time rather than comparing the base64 `String`s
+        // directly. The derived `PartialEq` short-circuits on the first differing byte, which
+        // would leak how many leading bytes of a guessed digest were correct.
+        let expected = base64::decode(&self.digest).map_err(|_| Error::InvalidDigest)?;
+        let actual = base64::decode(&digest.digest).map_err(|_| Error::InvalidDigest)?;
+
+        ring::constant_time::verify_slices_are_equal(&expected, &actual)
+            .map_err(|_| Error::InvalidDigest)
     }
 }
 
@@ -268,9 +405,139 @@ impl fmt::Display for Digest {
     }
 }
 
+/// Defines a list of `Digest`s parsed from a single `Digest` header.
+///
+/// Per RFC 3230 a `Digest` header may carry several comma-separated `algorithm=value` pairs, where
+/// a peer advertises more than one hash so the receiver can pick the strongest it understands. A
+/// `DigestList` parses every entry, silently skipping algorithm tokens this crate doesn't support,
+/// and verifies a body if *any* of the understood entries match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestList(Vec<Digest>);
+
+impl DigestList {
+    /// Creates a new `DigestList` by hashing a body once per requested `ShaSize`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use digest_headers::{DigestList, ShaSize};
+    /// let body = b"Some message body";
+    /// let digests = DigestList::new(body, &[ShaSize::TwoFiftySix, ShaSize::FiveTwelve]);
+    ///
+    /// println!("Digest: {}", digests.as_string());
+    /// ```
+    pub fn new(body: &[u8], sizes: &[ShaSize]) -> Self {
+        DigestList(sizes.iter().map(|&size| Digest::new(body, size)).collect())
+    }
+
+    /// Access the parsed `Digest`s.
+    pub fn digests(&self) -> &[Digest] {
+        &self.0
+    }
+
+    /// Represents the `DigestList` as a single comma-separated header `String`.
+    pub fn as_string(&self) -> String {
+        self.0
+            .iter()
+            .map(Digest::as_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Represents the `DigestList` as an RFC 9530 structured-field dictionary.
+    ///
+    /// Each member is rendered like `Digest::as_structured_field`, and members are joined with the
+    /// `, ` separator used by structured-field dictionaries.
+    pub fn as_structured_field(&self) -> String {
+        self.0
+            .iter()
+            .map(Digest::as_structured_field)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Parses an RFC 9530 structured-field dictionary into a `DigestList`.
+    ///
+    /// As with `from_str`, members whose algorithm key isn't understood are skipped rather than
+    /// treated as fatal.
+    pub fn parse_structured_field(s: &str) -> Result<Self, Error> {
+        let mut digests = Vec::new();
+
+        for member in s.split(',') {
+            let member = member.trim();
+
+            if member.is_empty() {
+                continue;
+            }
+
+            match Digest::parse_structured_field(member) {
+                Ok(digest) => digests.push(digest),
+                Err(Error::ParseShaSize) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(DigestList(digests))
+    }
+
+    /// Verify a given message body against the list.
+    ///
+    /// Verification succeeds as soon as one of the understood entries matches the recomputed hash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use digest_headers::{DigestList, ShaSize};
+    /// let body = b"Some message body";
+    /// let digests = DigestList::new(body, &[ShaSize::TwoFiftySix, ShaSize::FiveTwelve]);
+    ///
+    /// assert!(digests.verify(body).is_ok());
+    /// ```
+    pub fn verify(&self, body: &[u8]) -> Result<(), Error> {
+        for digest in &self.0 {
+            if digest.verify(body).is_ok() {
+                return Ok(());
+            }
+        }
+
+        Err(Error::InvalidDigest)
+    }
+}
+
+impl FromStr for DigestList {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut digests = Vec::new();
+
+        for entry in s.split(',') {
+            let entry = entry.trim();
+
+            if entry.is_empty() {
+                continue;
+            }
+
+            match entry.parse::<Digest>() {
+                Ok(digest) => digests.push(digest),
+                // An unknown algorithm token is skippable, not fatal.
+                Err(Error::ParseShaSize) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(DigestList(digests))
+    }
+}
+
+impl fmt::Display for DigestList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Digest, RequestBody, ShaSize};
+    use super::{Digest, DigestBuilder, DigestList, RequestBody, ShaSize};
 
     const D256: &'static str = "bFp1K/TT36l9YQ8frlh/cVGuWuFEy1rCUNpGwQCSEow=";
     const D384: &'static str = "wOx5d657W3O8k2P7SW18Y/Kj/Rqm02pzgFVBInHOj7hbc0IrYGVXwzid3vTH82um";
@@ -347,6 +614,124 @@ mod tests {
         parse_sha_ne("SHA-420");
     }
 
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn parse_sha3_256() {
+        parse_sha("SHA3-256");
+    }
+
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn parse_sha3_384() {
+        parse_sha("SHA3-384");
+    }
+
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn parse_sha3_512() {
+        parse_sha("SHA3-512");
+    }
+
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn verify_sha3_256() {
+        verify_round_trip(ShaSize::Sha3TwoFiftySix);
+    }
+
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn verify_sha3_384() {
+        verify_round_trip(ShaSize::Sha3ThreeEightyFour);
+    }
+
+    #[cfg(feature = "sha-3")]
+    #[test]
+    fn verify_sha3_512() {
+        verify_round_trip(ShaSize::Sha3FiveTwelve);
+    }
+
+    #[cfg(feature = "sha-3")]
+    fn verify_round_trip(sha_size: ShaSize) {
+        let body = b"The content of a thing";
+        let digest = Digest::new(body, sha_size);
+
+        assert!(digest.verify(body).is_ok());
+    }
+
+    #[test]
+    fn digest_list_round_trip() {
+        let body = b"The content of a thing";
+        let digests = DigestList::new(body, &[ShaSize::TwoFiftySix, ShaSize::FiveTwelve]);
+
+        assert!(digests.verify(body).is_ok());
+    }
+
+    #[test]
+    fn parse_digest_list_skips_unknown_algorithm() {
+        let raw = format!("MD5=not-supported,SHA-256={}", D256);
+        let digests = raw.parse::<DigestList>().unwrap();
+
+        assert_eq!(digests.digests().len(), 1);
+        assert!(digests.verify(b"The content of a thing").is_ok());
+    }
+
+    #[test]
+    fn digest_list_as_string_has_all_entries() {
+        let body = b"The content of a thing";
+        let digests = DigestList::new(body, &[ShaSize::TwoFiftySix, ShaSize::FiveTwelve]);
+
+        assert_eq!(digests.digests().len(), 2);
+        assert_eq!(digests.as_string().parse::<DigestList>().unwrap(), digests);
+    }
+
+    #[test]
+    fn streaming_builder_matches_oneshot_256() {
+        streaming_matches_oneshot(ShaSize::TwoFiftySix);
+    }
+
+    #[test]
+    fn streaming_builder_matches_oneshot_384() {
+        streaming_matches_oneshot(ShaSize::ThreeEightyFour);
+    }
+
+    #[test]
+    fn streaming_builder_matches_oneshot_512() {
+        streaming_matches_oneshot(ShaSize::FiveTwelve);
+    }
+
+    fn streaming_matches_oneshot(sha_size: ShaSize) {
+        let mut builder = DigestBuilder::new(sha_size);
+
+        builder.update(b"The content ");
+        builder.update(b"of a thing");
+
+        assert_eq!(builder.finalize(), Digest::new(b"The content of a thing", sha_size));
+    }
+
+    #[test]
+    fn structured_field_round_trip() {
+        let raw = format!("SHA-256={}", D256);
+        let digest = raw.parse::<Digest>().unwrap();
+
+        let structured = digest.as_structured_field();
+
+        assert_eq!(structured, format!("sha-256=:{}:", D256));
+        assert_eq!(Digest::parse_structured_field(&structured).unwrap(), digest);
+    }
+
+    #[test]
+    fn structured_field_list_round_trip() {
+        let body = b"The content of a thing";
+        let digests = DigestList::new(body, &[ShaSize::TwoFiftySix, ShaSize::FiveTwelve]);
+
+        let structured = digests.as_structured_field();
+
+        assert_eq!(
+            DigestList::parse_structured_field(&structured).unwrap(),
+            digests
+        );
+    }
+
     fn digest(provided: String, sha_size: ShaSize) {
         let some_body = b"The content of a thing";
         let body = RequestBody::new(some_body);
@@ -26,7 +26,7 @@ use std::error::Error as StdError;
 use std::fmt;
 use std::str::from_utf8;
 
-use {Digest, ShaSize};
+use {Digest, DigestBuilder, ShaSize};
 use prelude::*;
 
 /// The Error type for using Digests with Hyper.
@@ -173,11 +173,24 @@ impl IntoDigest for Body {
     type Error = Error;
 
     fn into_digest(self, sha_size: ShaSize) -> Result<(Self::Item, Digest), Self::Error> {
-        let full_body = self.concat2().wait()?;
+        // Fold each chunk into the running digest as it arrives rather than concatenating the
+        // whole body up front. We still collect the bytes so the body can be set again, since
+        // getting a Digest is non-destructive.
+        let builder = DigestBuilder::new(sha_size);
 
-        let digest = Digest::new(&full_body, sha_size);
+        let (bytes, builder) = self.fold(
+            (Vec::new(), builder),
+            |(mut bytes, mut builder), chunk| {
+                builder.update(&chunk);
+                bytes.extend_from_slice(&chunk);
 
-        Ok((full_body, digest))
+                Ok::<_, HyperError>((bytes, builder))
+            },
+        ).wait()?;
+
+        let digest = builder.finalize();
+
+        Ok((Chunk::from(bytes), digest))
     }
 }
 